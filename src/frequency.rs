@@ -0,0 +1,44 @@
+// Copyright 2016 rust-freqdist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! The `Frequency` trait captures the behavior shared by every frequency
+//! distribution backend in this crate, so generic code can be written once
+//! and run against whichever concrete type fits the caller's needs (hashed
+//! for speed, ordered for deterministic iteration).
+
+/// Common behavior of a frequency distribution: counting occurrences of a
+/// key and reading those counts back out.
+///
+/// `'a` is the lifetime of the borrow handed to [`iter_non_zero`], which
+/// needs to name the iterator's item lifetime in `NonZeroIter`.
+///
+/// [`iter_non_zero`]: #tymethod.iter_non_zero
+pub trait Frequency<'a, K: 'a> {
+  /// Iterator over the keys with a non-zero count, produced by
+  /// [`iter_non_zero`](#tymethod.iter_non_zero).
+  type NonZeroIter: Iterator<Item = &'a K>;
+
+  /// Updates the frequency of the key if it already exists. Otherwise,
+  /// inserts the key and sets its frequency to 1.
+  fn insert(&mut self, k: K);
+
+  /// Inserts a key with a starting count of `incr` if it does not exist.
+  /// If the key already exists, increments its count by `incr`.
+  fn insert_or_incr_by(&mut self, k: K, incr: usize);
+
+  /// Gets the frequency at which the key occurs, or `0` if it is absent.
+  fn get(&self, k: &K) -> usize;
+
+  /// Removes a key and its associated count.
+  fn remove(&mut self, k: &K);
+
+  /// Sum of the total number of items counted thus far.
+  fn sum_counts(&self) -> usize;
+
+  /// Iterator over the keys with a non-zero count.
+  fn iter_non_zero(&'a self) -> Self::NonZeroIter;
+}
@@ -5,12 +5,16 @@
 // http://opensource.org/licenses/MIT>, at your option. This file may not be
 // copied, modified, or distributed except according to those terms.
 
-//! Implementation of a Frequency Distribution in Rust. Keeps track of how many 
-//! times an object appears in a larger context (for example, how many times a 
-//! word appears in a piece of text). The underlying data structure of the 
+//! Implementation of a Frequency Distribution in Rust. Keeps track of how many
+//! times an object appears in a larger context (for example, how many times a
+//! word appears in a piece of text). The underlying data structure of the
 //! Frequency Distribution is a HashMap, so the object that is being counted
 //! must be hashable.
 //!
+//! Both `FrequencyDistribution` and the sorted-order [`OrderedFrequencyDistribution`]
+//! implement the [`Frequency`] trait, so generic code can be written once and run
+//! against either backend.
+//!
 //! # Example
 //!
 //! ```
@@ -30,13 +34,20 @@
 
 #[cfg(test)] extern crate test;
 
-use std::ops::Index;
+mod frequency;
+mod ordered;
+
+pub use frequency::Frequency;
+pub use ordered::{OrderedFrequencyDistribution, OrderedNonZeroKeysIter};
+
+use std::cmp::{self, Ordering, Reverse};
+use std::ops::{Add, BitAnd, BitOr, BitXor, Index, Sub};
 use std::default::Default;
 use std::hash::{Hasher, Hash, BuildHasher, SipHasher};
 use std::iter::{FromIterator, IntoIterator};
 use std::borrow::Borrow;
-use std::collections::HashMap;
-use std::collections::hash_map::{Keys, IntoIter, Iter, RandomState};
+use std::collections::{BinaryHeap, HashMap, TryReserveError};
+use std::collections::hash_map::{Entry as HashMapEntry, Keys, IntoIter, Iter, RandomState};
 
 
 static ZERO: usize = 0;
@@ -120,17 +131,84 @@ impl<K, H = SipHasher, S = RandomState> FrequencyDistribution<K, S>
     self.hashmap.len()
   }
 
+  /// Tries to reserve capacity for at least `additional` more keys to be
+  /// inserted. Unlike [`with_capacity`](#method.with_capacity), this can be
+  /// called on an existing distribution, and reports allocation failure
+  /// instead of aborting, so a caller streaming a large corpus with an
+  /// estimated key count can handle running out of memory gracefully.
+  #[inline(always)] pub fn try_reserve(
+    &mut self,
+    additional: usize
+  ) -> Result<(), TryReserveError> {
+    self.hashmap.try_reserve(additional)
+  }
+
   /// Gets the frequency in which the key occurs.
-  #[inline(always)] pub fn get<Q : ?Sized>(&self, k: &Q) -> usize 
+  #[inline(always)] pub fn get<Q : ?Sized>(&self, k: &Q) -> usize
     where K : Borrow<Q>, Q : Hash + Eq
   {
     self[k]
   }
 
-  /// Clears the counts of all keys and clears all keys from 
+  /// Gets the relative frequency at which the key occurs, as a fraction of
+  /// `sum_counts`. Returns `0.0` if the distribution is empty.
+  #[inline] pub fn freq<Q : ?Sized>(&self, k: &Q) -> f64
+    where K : Borrow<Q>, Q : Hash + Eq
+  {
+    if self.sum_counts == 0 { 0.0 } else { self.get(k) as f64 / self.sum_counts as f64 }
+  }
+
+  /// Returns the `n` keys with the highest counts, in descending order,
+  /// ties broken by the order `iter_non_zero` visits them in.
+  ///
+  /// For `n` smaller than the number of non-zero entries, this scans once
+  /// while maintaining a bounded min-heap of size `n`, which is `O(m log n)`
+  /// for `m` entries rather than the `O(m log m)` of sorting everything.
+  /// When `n` is at least as large as the distribution, it falls back to a
+  /// full sort.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// # use freqdist::FrequencyDistribution;
+  /// #
+  /// let mut fdist: FrequencyDistribution<&str> = FrequencyDistribution::new();
+  ///
+  /// fdist.entry("a").set(3);
+  /// fdist.entry("b").set(9);
+  /// fdist.entry("c").set(1);
+  ///
+  /// assert_eq!(fdist.most_common(2), vec![(&"b", 9), (&"a", 3)]);
+  /// ```
+  pub fn most_common(&self, n: usize) -> Vec<(&K, usize)> {
+    if n == 0 { return Vec::new(); }
+
+    if n >= self.len() {
+      let mut all: Vec<(&K, usize)> = self.iter_non_zero()
+        .map(|k| (k, self.get(k)))
+        .collect();
+
+      all.sort_by(|a, b| b.1.cmp(&a.1));
+
+      return all;
+    }
+
+    let mut heap: BinaryHeap<Reverse<HeapEntry<K>>> = BinaryHeap::with_capacity(n + 1);
+
+    for (index, k) in self.iter_non_zero().enumerate() {
+      heap.push(Reverse(HeapEntry { count: self.get(k), index, key: k }));
+
+      if heap.len() > n { heap.pop(); }
+    }
+
+    heap.into_sorted_vec().into_iter().map(|Reverse(e)| (e.key, e.count)).collect()
+  }
+
+  /// Clears the counts of all keys and clears all keys from
   /// the distribution.
   #[inline(always)] pub fn clear(&mut self) {
-    self.hashmap.clear()
+    self.hashmap.clear();
+    self.sum_counts = 0;
   }
 
   /// Updates the frequency of the value found with the key if it 
@@ -150,10 +228,30 @@ impl<K, H = SipHasher, S = RandomState> FrequencyDistribution<K, S>
     }
   }
 
+  /// Gets a handle to a key's count, for in-place adjustments that keep
+  /// `sum_counts` consistent with a single lookup.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// # use freqdist::FrequencyDistribution;
+  /// #
+  /// let mut fdist: FrequencyDistribution<&str> = FrequencyDistribution::new();
+  ///
+  /// fdist.entry("hello").and_incr_by(3);
+  /// fdist.entry("hello").set(10);
+  ///
+  /// assert_eq!(fdist.get(&"hello"), 10);
+  /// assert_eq!(fdist.sum_counts(), 10);
+  /// ```
+  #[inline(always)] pub fn entry(&mut self, k: K) -> FrequencyEntry<K, S> {
+    FrequencyEntry { dist: self, key: k }
+  }
+
   /// Inserts a value sizeo the hashmap if it does not exist with a new quantity
-  /// specified by the increment. If the value already exists, increments by 
+  /// specified by the increment. If the value already exists, increments by
   /// the specified amount.
-  #[inline] fn insert_or_incr_by(&mut self, k: K, incr: usize) {
+  #[inline] pub(crate) fn insert_or_incr_by(&mut self, k: K, incr: usize) {
     if !self.hashmap.contains_key(&k) {
       self.hashmap.insert(k, incr);
     } else {
@@ -272,6 +370,38 @@ impl<K, H, S> IntoIterator for FrequencyDistribution<K, S>
   }
 }
 
+impl<'a, K, H, S> Frequency<'a, K> for FrequencyDistribution<K, S>
+  where K : Eq + Hash + 'a,
+        H : Hasher,
+        S : BuildHasher<Hasher = H>
+{
+  type NonZeroIter = NonZeroKeysIter<'a, K>;
+
+  #[inline(always)] fn insert(&mut self, k: K) {
+    FrequencyDistribution::insert(self, k);
+  }
+
+  #[inline(always)] fn insert_or_incr_by(&mut self, k: K, incr: usize) {
+    FrequencyDistribution::insert_or_incr_by(self, k, incr);
+  }
+
+  #[inline(always)] fn get(&self, k: &K) -> usize {
+    self[k]
+  }
+
+  #[inline(always)] fn remove(&mut self, k: &K) {
+    FrequencyDistribution::remove(self, k);
+  }
+
+  #[inline(always)] fn sum_counts(&self) -> usize {
+    FrequencyDistribution::sum_counts(self)
+  }
+
+  #[inline(always)] fn iter_non_zero(&'a self) -> NonZeroKeysIter<'a, K> {
+    FrequencyDistribution::iter_non_zero(self)
+  }
+}
+
 impl<'a, K, H, S, Q : ?Sized> Index<&'a Q> for FrequencyDistribution<K, S>
   where K : Eq + Hash + Borrow<Q>,
         H : Hasher,
@@ -285,6 +415,125 @@ impl<'a, K, H, S, Q : ?Sized> Index<&'a Q> for FrequencyDistribution<K, S>
   }
 }
 
+/// Merges the entries of `other` into `result` by summing counts, the way
+/// `Add`/`BitOr` combine two distributions.
+#[inline] fn union<K, H, S>(
+  result: &mut FrequencyDistribution<K, S>,
+  other: &FrequencyDistribution<K, S>
+) where K : Eq + Hash + Clone,
+        H : Hasher,
+        S : BuildHasher<Hasher = H>
+{
+  for (k, &count) in other.iter() { result.insert_or_incr_by(k.clone(), count); }
+}
+
+impl<'a, 'b, K, H, S> Add<&'b FrequencyDistribution<K, S>> for &'a FrequencyDistribution<K, S>
+  where K : Eq + Hash + Clone,
+        H : Hasher + Default,
+        S : BuildHasher<Hasher = H> + Default
+{
+  type Output = FrequencyDistribution<K, S>;
+
+  /// Combines two distributions by summing the counts of shared keys, and
+  /// keeping the counts of keys that only appear in one of the two.
+  fn add(self, other: &'b FrequencyDistribution<K, S>) -> FrequencyDistribution<K, S> {
+    let mut result: FrequencyDistribution<K, S> = self.iter()
+      .map(|(k, &c)| (k.clone(), c))
+      .collect();
+
+    union(&mut result, other);
+
+    result
+  }
+}
+
+impl<'a, 'b, K, H, S> BitOr<&'b FrequencyDistribution<K, S>> for &'a FrequencyDistribution<K, S>
+  where K : Eq + Hash + Clone,
+        H : Hasher + Default,
+        S : BuildHasher<Hasher = H> + Default
+{
+  type Output = FrequencyDistribution<K, S>;
+
+  /// Equivalent to `Add`: combines two distributions by summing the counts
+  /// of shared keys, and keeping the counts of keys that only appear in one
+  /// of the two.
+  #[inline] fn bitor(self, other: &'b FrequencyDistribution<K, S>) -> FrequencyDistribution<K, S> {
+    self.add(other)
+  }
+}
+
+impl<'a, 'b, K, H, S> Sub<&'b FrequencyDistribution<K, S>> for &'a FrequencyDistribution<K, S>
+  where K : Eq + Hash + Clone,
+        H : Hasher + Default,
+        S : BuildHasher<Hasher = H> + Default
+{
+  type Output = FrequencyDistribution<K, S>;
+
+  /// Produces the saturating count difference of two distributions: for
+  /// every key in `self`, subtracts the count found in `other` (treating a
+  /// missing key as `0`), dropping keys whose count reaches zero.
+  fn sub(self, other: &'b FrequencyDistribution<K, S>) -> FrequencyDistribution<K, S> {
+    let mut result: FrequencyDistribution<K, S> = FrequencyDistribution::with_capacity(self.len());
+
+    for (k, &count) in self.iter() {
+      let diff = count.saturating_sub(other.get(k));
+
+      if diff > 0 { result.insert_or_incr_by(k.clone(), diff); }
+    }
+
+    result
+  }
+}
+
+impl<'a, 'b, K, H, S> BitAnd<&'b FrequencyDistribution<K, S>> for &'a FrequencyDistribution<K, S>
+  where K : Eq + Hash + Clone,
+        H : Hasher + Default,
+        S : BuildHasher<Hasher = H> + Default
+{
+  type Output = FrequencyDistribution<K, S>;
+
+  /// Keeps only the keys shared by both distributions, with each key's
+  /// count set to the minimum of the two.
+  fn bitand(self, other: &'b FrequencyDistribution<K, S>) -> FrequencyDistribution<K, S> {
+    let mut result: FrequencyDistribution<K, S> = FrequencyDistribution::with_capacity(self.len());
+
+    for (k, &count) in self.iter() {
+      let other_count = other.get(k);
+
+      if count > 0 && other_count > 0 {
+        result.insert_or_incr_by(k.clone(), cmp::min(count, other_count));
+      }
+    }
+
+    result
+  }
+}
+
+impl<'a, 'b, K, H, S> BitXor<&'b FrequencyDistribution<K, S>> for &'a FrequencyDistribution<K, S>
+  where K : Eq + Hash + Clone,
+        H : Hasher + Default,
+        S : BuildHasher<Hasher = H> + Default
+{
+  type Output = FrequencyDistribution<K, S>;
+
+  /// Keeps only the keys unique to one side or the other, dropping keys
+  /// shared by both distributions, with each kept key's original count.
+  fn bitxor(self, other: &'b FrequencyDistribution<K, S>) -> FrequencyDistribution<K, S> {
+    let mut result: FrequencyDistribution<K, S> =
+      FrequencyDistribution::with_capacity(self.len() + other.len());
+
+    for (k, &count) in self.iter() {
+      if count > 0 && other.get(k) == 0 { result.insert_or_incr_by(k.clone(), count); }
+    }
+
+    for (k, &count) in other.iter() {
+      if count > 0 && self.get(k) == 0 { result.insert_or_incr_by(k.clone(), count); }
+    }
+
+    result
+  }
+}
+
 /// Iterator over entries with non-zero quantities.
 pub struct NonZeroKeysIter<'a, K: 'a> {
   iter: Iter<'a, K, usize> 
@@ -304,6 +553,102 @@ impl<'a, K: 'a> Iterator for NonZeroKeysIter<'a, K> {
   }
 }
 
+/// A `(count, key)` pair ordered by `count`, falling back to `index` (the
+/// position `iter_non_zero` visited it at) to break ties, used to drive the
+/// bounded min-heap in [`FrequencyDistribution::most_common`]. An earlier
+/// `index` ranks higher, so ties resolve the same way the full-sort fallback
+/// resolves them: in `iter_non_zero` order.
+struct HeapEntry<'a, K: 'a> {
+  count: usize,
+  index: usize,
+  key: &'a K
+}
+
+impl<'a, K: 'a> PartialEq for HeapEntry<'a, K> {
+  #[inline] fn eq(&self, other: &HeapEntry<'a, K>) -> bool {
+    self.count == other.count && self.index == other.index
+  }
+}
+
+impl<'a, K: 'a> Eq for HeapEntry<'a, K> {}
+
+impl<'a, K: 'a> PartialOrd for HeapEntry<'a, K> {
+  #[inline] fn partial_cmp(&self, other: &HeapEntry<'a, K>) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<'a, K: 'a> Ord for HeapEntry<'a, K> {
+  #[inline] fn cmp(&self, other: &HeapEntry<'a, K>) -> Ordering {
+    self.count.cmp(&other.count).then_with(|| other.index.cmp(&self.index))
+  }
+}
+
+/// A handle to a key's count within a [`FrequencyDistribution`], obtained
+/// via [`FrequencyDistribution::entry`]. Mutating through the handle keeps
+/// `sum_counts` consistent with a single lookup, instead of requiring a
+/// separate `remove` and `insert` to change a key's count arbitrarily.
+pub struct FrequencyEntry<'a, K: 'a, S: 'a = RandomState> {
+  dist: &'a mut FrequencyDistribution<K, S>,
+  key: K
+}
+
+impl<'a, K, H, S> FrequencyEntry<'a, K, S>
+  where K : Eq + Hash,
+        H : Hasher,
+        S : BuildHasher<Hasher = H>
+{
+  /// Increments the key's count by `n`, inserting it with a count of `n` if
+  /// it is not already present. Returns the key's new count.
+  pub fn and_incr_by(self, n: usize) -> usize {
+    match self.dist.hashmap.entry(self.key) {
+      HashMapEntry::Occupied(mut e) => {
+        *e.get_mut() += n;
+        self.dist.sum_counts += n;
+        *e.get()
+      },
+      HashMapEntry::Vacant(e) => {
+        self.dist.sum_counts += n;
+        *e.insert(n)
+      }
+    }
+  }
+
+  /// Sets the key's count to the absolute value `n`, inserting it if it is
+  /// not already present. Returns `n`.
+  pub fn set(self, n: usize) -> usize {
+    match self.dist.hashmap.entry(self.key) {
+      HashMapEntry::Occupied(mut e) => {
+        let old = *e.get();
+
+        *e.get_mut() = n;
+
+        if n >= old { self.dist.sum_counts += n - old; }
+        else { self.dist.sum_counts -= old - n; }
+      },
+      HashMapEntry::Vacant(e) => {
+        e.insert(n);
+        self.dist.sum_counts += n;
+      }
+    }
+
+    n
+  }
+
+  /// Inserts the key with a count of `n` if it is not already present,
+  /// leaving an existing count untouched. Returns the key's resulting
+  /// count.
+  pub fn or_insert(self, n: usize) -> usize {
+    match self.dist.hashmap.entry(self.key) {
+      HashMapEntry::Occupied(e) => *e.get(),
+      HashMapEntry::Vacant(e) => {
+        self.dist.sum_counts += n;
+        *e.insert(n)
+      }
+    }
+  }
+}
+
 #[test]
 fn smoke_test_frequency_distribution_insert() {
   let words = vec!("alpha", "beta");
@@ -354,6 +699,21 @@ fn smoke_test_frequency_distribution_remove() {
   assert_eq!(dist.sum_counts(), 125);
 }
 
+#[test]
+fn smoke_test_frequency_distribution_clear() {
+  let words = vec!(("a", 7usize), ("b", 5usize));
+  let mut dist: FrequencyDistribution<&str> = FromIterator::from_iter(words.into_iter());
+
+  dist.clear();
+
+  assert_eq!(dist.len(), 0);
+  assert_eq!(dist.sum_counts(), 0);
+
+  dist.insert("a");
+
+  assert_eq!(dist.sum_counts(), 1);
+}
+
 #[test]
 fn smoke_test_frequency_sum_counts() {
   let words = vec!(("a", 7usize), ("b", 5usize), ("c", 8usize), ("d", 3usize));
@@ -365,3 +725,131 @@ fn smoke_test_frequency_sum_counts() {
 
   assert_eq!(dist.sum_counts(), 24);
 }
+
+#[test]
+fn smoke_test_frequency_distribution_freq() {
+  let empty: FrequencyDistribution<&str> = FrequencyDistribution::new();
+
+  assert_eq!(empty.freq(&"a"), 0.0);
+
+  let words = vec!(("a", 1usize), ("b", 3usize));
+  let dist: FrequencyDistribution<&str> = FromIterator::from_iter(words.into_iter());
+
+  assert_eq!(dist.freq(&"a"), 0.25);
+  assert_eq!(dist.freq(&"b"), 0.75);
+  assert_eq!(dist.freq(&"c"), 0.0);
+}
+
+#[test]
+fn smoke_test_frequency_distribution_most_common() {
+  let words = vec!(("a", 3usize), ("b", 9usize), ("c", 1usize), ("d", 5usize));
+  let dist: FrequencyDistribution<&str> = FromIterator::from_iter(words.into_iter());
+
+  assert_eq!(dist.most_common(0), Vec::<(&&str, usize)>::new());
+  assert_eq!(dist.most_common(2), vec![(&"b", 9), (&"d", 5)]);
+  assert_eq!(dist.most_common(10).len(), 4);
+  assert_eq!(dist.most_common(10)[0], (&"b", 9));
+}
+
+#[test]
+fn smoke_test_frequency_distribution_most_common_ties() {
+  let words: Vec<(&str, usize)> = vec!("a", "b", "c", "d", "e", "f", "g", "h")
+    .into_iter()
+    .map(|k| (k, 5usize))
+    .collect();
+  let dist: FrequencyDistribution<&str> = FromIterator::from_iter(words.into_iter());
+
+  // All counts are tied, so the heap path (n < len()) must agree with the
+  // order `iter_non_zero` visits keys in, the same way the `n >= len()`
+  // fallback's stable sort does.
+  let expected: Vec<(&&str, usize)> = dist.iter_non_zero()
+    .take(3)
+    .map(|k| (k, dist.get(k)))
+    .collect();
+
+  assert_eq!(dist.most_common(3), expected);
+}
+
+#[test]
+fn smoke_test_frequency_distribution_entry() {
+  let mut dist: FrequencyDistribution<&str> = FrequencyDistribution::new();
+
+  assert_eq!(dist.entry("a").and_incr_by(3), 3);
+  assert_eq!(dist.entry("a").and_incr_by(2), 5);
+  assert_eq!(dist.entry("a").or_insert(100), 5);
+  assert_eq!(dist.entry("b").or_insert(7), 7);
+
+  assert_eq!(dist.sum_counts(), 12);
+
+  assert_eq!(dist.entry("a").set(1), 1);
+
+  assert_eq!(dist.get(&"a"), 1);
+  assert_eq!(dist.sum_counts(), 8);
+}
+
+#[test]
+fn smoke_test_frequency_distribution_try_reserve() {
+  let mut dist: FrequencyDistribution<&str> = FrequencyDistribution::new();
+
+  assert!(dist.try_reserve(16).is_ok());
+
+  dist.insert("a");
+
+  assert_eq!(dist.get(&"a"), 1);
+}
+
+#[test]
+fn smoke_test_frequency_distribution_add() {
+  let a: FrequencyDistribution<&str> =
+    FromIterator::from_iter(vec!(("a", 3usize), ("b", 2usize)).into_iter());
+  let b: FrequencyDistribution<&str> =
+    FromIterator::from_iter(vec!(("b", 4usize), ("c", 1usize)).into_iter());
+  let union = &a + &b;
+
+  assert_eq!(union.get(&"a"), 3);
+  assert_eq!(union.get(&"b"), 6);
+  assert_eq!(union.get(&"c"), 1);
+  assert_eq!(union.sum_counts(), 10);
+}
+
+#[test]
+fn smoke_test_frequency_distribution_sub() {
+  let a: FrequencyDistribution<&str> =
+    FromIterator::from_iter(vec!(("a", 5usize), ("b", 2usize)).into_iter());
+  let b: FrequencyDistribution<&str> =
+    FromIterator::from_iter(vec!(("a", 3usize), ("b", 2usize), ("c", 7usize)).into_iter());
+  let diff = &a - &b;
+
+  assert_eq!(diff.get(&"a"), 2);
+  assert_eq!(diff.get(&"b"), 0);
+  assert_eq!(diff.get(&"c"), 0);
+  assert_eq!(diff.sum_counts(), 2);
+}
+
+#[test]
+fn smoke_test_frequency_distribution_bitand() {
+  let a: FrequencyDistribution<&str> =
+    FromIterator::from_iter(vec!(("a", 5usize), ("b", 2usize)).into_iter());
+  let b: FrequencyDistribution<&str> =
+    FromIterator::from_iter(vec!(("a", 3usize), ("b", 9usize), ("c", 7usize)).into_iter());
+  let shared = &a & &b;
+
+  assert_eq!(shared.get(&"a"), 3);
+  assert_eq!(shared.get(&"b"), 2);
+  assert_eq!(shared.get(&"c"), 0);
+  assert_eq!(shared.sum_counts(), 5);
+}
+
+#[test]
+fn smoke_test_frequency_distribution_bitxor() {
+  let a: FrequencyDistribution<&str> =
+    FromIterator::from_iter(vec!(("a", 5usize), ("b", 2usize)).into_iter());
+  let b: FrequencyDistribution<&str> =
+    FromIterator::from_iter(vec!(("a", 3usize), ("b", 9usize), ("c", 7usize)).into_iter());
+  let unique = &a ^ &b;
+
+  assert_eq!(unique.get(&"a"), 0);
+  assert_eq!(unique.get(&"b"), 0);
+  assert_eq!(unique.get(&"c"), 7);
+  assert_eq!(unique.sum_counts(), 7);
+}
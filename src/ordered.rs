@@ -0,0 +1,340 @@
+// Copyright 2016 rust-freqdist Developers
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or
+// http://apache.org/licenses/LICENSE-2.0> or the MIT license <LICENSE-MIT or
+// http://opensource.org/licenses/MIT>, at your option. This file may not be
+// copied, modified, or distributed except according to those terms.
+
+//! A `BTreeMap`-backed frequency distribution, for callers who need keys
+//! visited in sorted order (deterministic output, range queries) and are
+//! willing to require `K: Ord` instead of `K: Hash`.
+
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::collections::btree_map::{Iter, Keys, Range};
+use std::iter::{FromIterator, IntoIterator};
+use std::ops::{Index, RangeBounds};
+
+use frequency::Frequency;
+
+static ZERO: usize = 0;
+
+/// A frequency distribution backed by a `BTreeMap`, keeping keys in sorted
+/// order.
+///
+/// # Example
+///
+/// ```
+/// # use freqdist::{Frequency, OrderedFrequencyDistribution};
+/// #
+/// let mut fdist: OrderedFrequencyDistribution<&str> = OrderedFrequencyDistribution::new();
+///
+/// fdist.insert("hello");
+/// fdist.insert("hello");
+/// fdist.insert("goodbye");
+///
+/// assert_eq!(fdist.get(&"hello"), 2);
+/// ```
+#[allow(missing_docs)] pub struct OrderedFrequencyDistribution<K> {
+  btreemap: BTreeMap<K, usize>,
+  sum_counts: usize
+}
+
+impl<K> OrderedFrequencyDistribution<K> where K : Ord {
+  /// Creates a new, empty `OrderedFrequencyDistribution`.
+  #[inline(always)] pub fn new() -> OrderedFrequencyDistribution<K> {
+    OrderedFrequencyDistribution {
+      btreemap: BTreeMap::new(),
+      sum_counts: 0
+    }
+  }
+
+  /// Iterator over the keys.
+  #[inline(always)] pub fn keys(&self) -> Keys<K, usize> {
+    self.btreemap.keys()
+  }
+
+  /// Iterator over the key, frequency pairs, in sorted key order.
+  #[inline(always)] pub fn iter(&self) -> Iter<K, usize> {
+    self.btreemap.iter()
+  }
+
+  /// Iterator over the non-zero frequency keys.
+  #[inline(always)] pub fn iter_non_zero(&self) -> OrderedNonZeroKeysIter<K> {
+    OrderedNonZeroKeysIter { iter: self.iter() }
+  }
+
+  /// Iterator over the key, frequency pairs whose keys fall within `range`,
+  /// in sorted key order. This is not available on the hash-backed
+  /// `FrequencyDistribution`, since its keys have no ordering to range over.
+  ///
+  /// # Example
+  ///
+  /// ```
+  /// # use freqdist::{Frequency, OrderedFrequencyDistribution};
+  /// #
+  /// let mut fdist: OrderedFrequencyDistribution<u32> = OrderedFrequencyDistribution::new();
+  ///
+  /// fdist.insert(1);
+  /// fdist.insert(5);
+  /// fdist.insert(9);
+  ///
+  /// assert_eq!(fdist.range(2..9).count(), 1);
+  /// ```
+  #[inline(always)] pub fn range<R>(&self, range: R) -> Range<K, usize>
+    where R : RangeBounds<K>
+  {
+    self.btreemap.range(range)
+  }
+
+  /// Sum of the total number of items counted thus far.
+  #[inline(always)] pub fn sum_counts(&self) -> usize {
+    self.sum_counts
+  }
+
+  /// Returns the number of entries in the distribution.
+  #[inline(always)] pub fn len(&self) -> usize {
+    self.btreemap.len()
+  }
+
+  /// Gets the frequency in which the key occurs.
+  #[inline(always)] pub fn get<Q : ?Sized>(&self, k: &Q) -> usize
+    where K : Borrow<Q>, Q : Ord
+  {
+    self[k]
+  }
+
+  /// Clears the counts of all keys and clears all keys from the
+  /// distribution.
+  #[inline(always)] pub fn clear(&mut self) {
+    self.btreemap.clear();
+    self.sum_counts = 0;
+  }
+
+  /// Updates the frequency of the key if it already exists. Otherwise,
+  /// inserts the key, and sets its frequency to 1.
+  #[inline(always)] pub fn insert(&mut self, k: K) {
+    self.insert_or_incr_by(k, 1);
+  }
+
+  /// Removes a key and its associated count.
+  #[inline(always)] pub fn remove<Q : ?Sized>(&mut self, k: &Q)
+    where K : Borrow<Q>, Q : Ord
+  {
+    match self.btreemap.remove(k) {
+      Some(count) => self.sum_counts -= count,
+      None => ()
+    }
+  }
+
+  /// Inserts a key into the map if it does not exist with a new quantity
+  /// specified by the increment. If the key already exists, increments by
+  /// the specified amount.
+  #[inline] pub(crate) fn insert_or_incr_by(&mut self, k: K, incr: usize) {
+    if !self.btreemap.contains_key(&k) {
+      self.btreemap.insert(k, incr);
+    } else {
+      *self.btreemap.get_mut(&k).unwrap() += incr;
+    }
+
+    self.sum_counts += incr;
+  }
+}
+
+impl<K> Default for OrderedFrequencyDistribution<K> where K : Ord {
+  /// Creates a default `OrderedFrequencyDistribution`.
+  #[inline(always)] fn default() -> OrderedFrequencyDistribution<K> {
+    OrderedFrequencyDistribution::new()
+  }
+}
+
+impl<'a, K> Frequency<'a, K> for OrderedFrequencyDistribution<K> where K : Ord + 'a {
+  type NonZeroIter = OrderedNonZeroKeysIter<'a, K>;
+
+  #[inline(always)] fn insert(&mut self, k: K) {
+    OrderedFrequencyDistribution::insert(self, k);
+  }
+
+  #[inline(always)] fn insert_or_incr_by(&mut self, k: K, incr: usize) {
+    OrderedFrequencyDistribution::insert_or_incr_by(self, k, incr);
+  }
+
+  #[inline(always)] fn get(&self, k: &K) -> usize {
+    OrderedFrequencyDistribution::get(self, k)
+  }
+
+  #[inline(always)] fn remove(&mut self, k: &K) {
+    OrderedFrequencyDistribution::remove(self, k);
+  }
+
+  #[inline(always)] fn sum_counts(&self) -> usize {
+    OrderedFrequencyDistribution::sum_counts(self)
+  }
+
+  #[inline(always)] fn iter_non_zero(&'a self) -> OrderedNonZeroKeysIter<'a, K> {
+    OrderedFrequencyDistribution::iter_non_zero(self)
+  }
+}
+
+impl<K> FromIterator<(K, usize)> for OrderedFrequencyDistribution<K> where K : Ord {
+  /// Iterates through an iterator, and creates a new
+  /// `OrderedFrequencyDistribution` from it. The iterator should be an
+  /// iterator over keys and frequencies.
+  fn from_iter<T>(iter: T) -> OrderedFrequencyDistribution<K>
+    where T : IntoIterator<Item = (K, usize)>
+  {
+    let mut fdist = OrderedFrequencyDistribution::new();
+
+    for (k, freq) in iter { fdist.insert_or_incr_by(k, freq); }
+
+    fdist
+  }
+}
+
+impl<K> Extend<(K, usize)> for OrderedFrequencyDistribution<K> where K : Ord {
+  /// Extends the distribution by adding the keys or updating the
+  /// frequencies of the keys.
+  fn extend<T>(&mut self, iter: T)
+    where T : IntoIterator<Item = (K, usize)>
+  {
+    for (k, freq) in iter { self.insert_or_incr_by(k, freq); }
+  }
+}
+
+impl<'a, K, Q : ?Sized> Index<&'a Q> for OrderedFrequencyDistribution<K>
+  where K : Ord + Borrow<Q>,
+        Q : Ord
+{
+  type Output = usize;
+
+  #[inline] fn index<'b>(&'b self, index: &Q) -> &'b usize {
+    self.btreemap.get(index).unwrap_or(&ZERO)
+  }
+}
+
+/// Iterator over entries with non-zero quantities, in sorted key order.
+pub struct OrderedNonZeroKeysIter<'a, K: 'a> {
+  iter: Iter<'a, K, usize>
+}
+
+impl<'a, K: 'a> Iterator for OrderedNonZeroKeysIter<'a, K> {
+  type Item = &'a K;
+
+  #[inline(always)] fn next(&mut self) -> Option<&'a K> {
+    loop {
+      match self.iter.next() {
+        Some((k, c)) if *c > 0 => return Some(k),
+        None => return None,
+        _ => ()
+      }
+    }
+  }
+}
+
+#[test]
+fn smoke_test_ordered_frequency_distribution_insert() {
+  let words = vec!("alpha", "beta");
+  let mut dist: OrderedFrequencyDistribution<&str> = OrderedFrequencyDistribution::new();
+
+  dist.insert(words[0]);
+
+  assert_eq!(dist.get(&words[0]), 1);
+
+  dist.insert(words[1]);
+
+  assert_eq!(dist.get(&words[1]), 1);
+
+  for _ in 0..7u32 { dist.insert(words[0]); }
+
+  assert_eq!(dist.get(&words[0]), 8);
+}
+
+#[test]
+fn smoke_test_ordered_frequency_distribution_iter() {
+  let words = vec!(("a", 50usize), ("b", 100usize), ("c", 75usize), ("d", 0usize));
+  let dist: OrderedFrequencyDistribution<&str> = FromIterator::from_iter(words.into_iter());
+
+  assert_eq!(dist.get(&"a"), 50);
+  assert_eq!(dist.get(&"b"), 100);
+  assert_eq!(dist.get(&"c"), 75);
+
+  let mut iter = dist.iter_non_zero();
+
+  assert_eq!(iter.next(), Some(&"a"));
+  assert_eq!(iter.next(), Some(&"b"));
+  assert_eq!(iter.next(), Some(&"c"));
+  assert!(iter.next().is_none());
+
+  assert_eq!(dist.sum_counts(), 225);
+}
+
+#[test]
+fn smoke_test_ordered_frequency_distribution_remove() {
+  let words = vec!(("a", 50usize), ("b", 100usize), ("c", 25usize));
+  let mut dist: OrderedFrequencyDistribution<&str> = FromIterator::from_iter(words.into_iter());
+
+  assert_eq!(dist.get(&"a"), 50);
+
+  dist.remove(&"a");
+
+  assert_eq!(dist.get(&"a"), 0);
+  assert_eq!(dist.sum_counts(), 125);
+}
+
+#[test]
+fn smoke_test_ordered_frequency_distribution_clear() {
+  let words = vec!(("a", 7usize), ("b", 5usize));
+  let mut dist: OrderedFrequencyDistribution<&str> = FromIterator::from_iter(words.into_iter());
+
+  dist.clear();
+
+  assert_eq!(dist.len(), 0);
+  assert_eq!(dist.sum_counts(), 0);
+
+  dist.insert("a");
+
+  assert_eq!(dist.sum_counts(), 1);
+}
+
+#[test]
+fn smoke_test_ordered_frequency_distribution_extend() {
+  let mut dist: OrderedFrequencyDistribution<&str> = OrderedFrequencyDistribution::new();
+
+  dist.extend(vec!(("a", 3usize), ("b", 2usize)));
+  dist.extend(vec!(("a", 1usize)));
+
+  assert_eq!(dist.get(&"a"), 4);
+  assert_eq!(dist.get(&"b"), 2);
+  assert_eq!(dist.sum_counts(), 6);
+}
+
+#[test]
+fn smoke_test_ordered_frequency_distribution_index() {
+  let words = vec!(("a", 3usize), ("b", 2usize));
+  let dist: OrderedFrequencyDistribution<&str> = FromIterator::from_iter(words.into_iter());
+
+  assert_eq!(dist[&"a"], 3);
+  assert_eq!(dist[&"missing"], 0);
+}
+
+#[test]
+fn smoke_test_ordered_frequency_distribution_trait_impl() {
+  fn count_non_zero_via_trait<'a, K: 'a, F: Frequency<'a, K>>(f: &'a F) -> usize {
+    f.iter_non_zero().count()
+  }
+
+  let mut dist: OrderedFrequencyDistribution<&str> = OrderedFrequencyDistribution::new();
+
+  Frequency::insert(&mut dist, "a");
+  Frequency::insert_or_incr_by(&mut dist, "a", 4);
+  Frequency::insert(&mut dist, "b");
+
+  assert_eq!(Frequency::get(&dist, &"a"), 5);
+  assert_eq!(Frequency::sum_counts(&dist), 6);
+  assert_eq!(count_non_zero_via_trait(&dist), 2);
+
+  Frequency::remove(&mut dist, &"b");
+
+  assert_eq!(Frequency::get(&dist, &"b"), 0);
+  assert_eq!(Frequency::sum_counts(&dist), 5);
+}